@@ -1,13 +1,139 @@
 use commands::{self, Command};
 use errors::*;
-use input::Key;
+use input::{Key, KeyCode, Modifiers};
 use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 use std::convert::Into;
 use yaml::{Yaml, YamlLoader};
 
+/// One or more commands bound to a single key (or chord), run in order.
+/// Most bindings are a single command; a YAML list binds several at once
+/// (e.g. `d: [selection::copy, buffer::delete_selection]`). The caller is
+/// expected to run each command in turn, aborting the rest if one errors.
+#[derive(Clone, PartialEq)]
+pub struct CommandGroup(Vec<Command>);
+
+impl CommandGroup {
+    fn single(command: Command) -> CommandGroup {
+        CommandGroup(vec![command])
+    }
+}
+
+impl Deref for CommandGroup {
+    type Target = Vec<Command>;
+
+    fn deref(&self) -> &Vec<Command> {
+        &self.0
+    }
+}
+
+/// A single level of a mode's keybinding tree. A `Leaf` is a terminal
+/// binding, reached once the whole key sequence has been entered. A `Node`
+/// is an intermediate level, reached while a multi-key chord (e.g. `g g`)
+/// is still being typed.
+#[derive(Clone)]
+pub enum KeyTrie {
+    Leaf(CommandGroup),
+    Node(KeyTrieNode),
+}
+
+/// An intermediate level of a key trie: its child bindings, in both
+/// lookup (`HashMap`) and YAML declaration (`Vec`) order, plus an
+/// optional which-key-style label describing the submenu (e.g. "Goto").
+/// A `sticky` node (`sticky: true` in YAML) stays active as the root for
+/// subsequent keys once entered, instead of resetting after each match;
+/// see `KeyMap::feed`.
+#[derive(Clone)]
+pub struct KeyTrieNode {
+    pub label: Option<String>,
+    pub sticky: bool,
+    order: Vec<Key>,
+    bindings: HashMap<Key, KeyTrie>,
+}
+
+impl KeyTrieNode {
+    fn new() -> KeyTrieNode {
+        KeyTrieNode {
+            label: None,
+            sticky: false,
+            order: Vec::new(),
+            bindings: HashMap::new(),
+        }
+    }
+
+    /// Binds a key to a trie entry, recording it in declaration order.
+    fn insert(&mut self, key: Key, trie: KeyTrie) {
+        if !self.bindings.contains_key(&key) {
+            self.order.push(key);
+        }
+
+        self.bindings.insert(key, trie);
+    }
+
+    fn get(&self, key: &Key) -> Option<&KeyTrie> {
+        self.bindings.get(key)
+    }
+
+    /// The node's children, in the order they were declared in YAML.
+    fn iter(&self) -> impl Iterator<Item = (&Key, &KeyTrie)> {
+        self.order.iter().filter_map(move |key| {
+            self.bindings.get(key).map(|trie| (key, trie))
+        })
+    }
+
+    /// Removes and returns all of the node's child bindings.
+    fn drain(&mut self) -> Vec<(Key, KeyTrie)> {
+        let keys = self.order.drain(..).collect::<Vec<_>>();
+
+        keys.into_iter()
+            .filter_map(|key| self.bindings.remove(&key).map(|trie| (key, trie)))
+            .collect()
+    }
+}
+
+/// The outcome of feeding a key into a `KeyTrie` lookup.
+#[derive(Clone, PartialEq)]
+pub enum KeyMatch {
+    /// The full sequence resolved to a command group.
+    Matched(CommandGroup),
+    /// The sequence so far is a valid prefix of a longer chord; the caller
+    /// should hold onto its pending keys and wait for another keypress.
+    Pending,
+    /// The sequence doesn't match anything; the caller should flush its
+    /// pending keys and start over.
+    NotFound,
+}
+
+/// Caller-held state for a mode's in-progress chord input, used with
+/// `KeyMap::feed`. Tracks the keys typed since the last match or dead
+/// end, plus the path (from the mode root) to the currently active
+/// sticky submenu, if any. `Key::Esc` clears both; an ordinary match or
+/// dead end clears only the pending keys, so a sticky submenu survives
+/// repeated commands until the user backs out of it.
+#[derive(Clone, Default)]
+pub struct ChordState {
+    sticky_path: Vec<Key>,
+    pending: Vec<Key>,
+}
+
+impl ChordState {
+    pub fn new() -> ChordState {
+        ChordState::default()
+    }
+
+    /// The keys typed so far that haven't yet resolved to a match or dead end.
+    pub fn pending(&self) -> &[Key] {
+        &self.pending
+    }
+
+    /// Whether a sticky submenu is currently active.
+    pub fn is_sticky(&self) -> bool {
+        !self.sticky_path.is_empty()
+    }
+}
+
 /// Nested HashMap newtype that provides a more ergonomic interface.
-pub struct KeyMap(HashMap<String, HashMap<Key, Command>>);
+pub struct KeyMap(HashMap<String, KeyTrie>);
 
 impl KeyMap {
     /// Parses a Yaml tree of modes and their keybindings into a complete keymap.
@@ -19,7 +145,7 @@ impl KeyMap {
     ///
     /// becomes this HashMap entry:
     ///
-    ///   "normal" => { Key::Ctrl('r') => commands::cursor::move_up }
+    ///   "normal" => Node({ Key::Ctrl('r') => Leaf(commands::cursor::move_up) })
     ///
     pub fn from(keymap_data: &Yaml) -> Result<KeyMap> {
         let modes = keymap_data.as_hash().ok_or(
@@ -41,20 +167,194 @@ impl KeyMap {
         Ok(KeyMap(keymap))
     }
 
-    /// Searches the keymap for the specified key.
-    /// Character keys will fall back to wildcard character bindings
-    /// if the specific character binding cannot be found.
+    /// Feeds a sequence of keys through a mode's key trie, one at a time,
+    /// returning whether they matched a command, are a valid (but
+    /// incomplete) chord prefix, or don't lead anywhere. Character keys
+    /// will fall back to wildcard character bindings at each level of the
+    /// trie if a specific character binding isn't found there.
     ///
-    pub fn command_for(&self, mode: &str, key: &Key) -> Option<Command> {
-        self.0.get(mode).and_then(|mode_keymap| {
-            if let &Key::Char(_) = key {
-                // Look for a command for this specific character, falling
-                // back to another search for a wildcard character binding.
-                mode_keymap.get(key).or_else(|| mode_keymap.get(&Key::AnyChar))
-            } else {
-                mode_keymap.get(key)
+    pub fn lookup(&self, mode: &str, keys: &[Key]) -> KeyMatch {
+        let mut node = match self.0.get(mode) {
+            Some(node) => node,
+            None => return KeyMatch::NotFound,
+        };
+
+        for key in keys {
+            match KeyMap::step(node, key) {
+                Some(&KeyTrie::Leaf(ref group)) => return KeyMatch::Matched(group.clone()),
+                Some(next_node @ &KeyTrie::Node(_)) => node = next_node,
+                None => return KeyMatch::NotFound,
             }
-        }).map(|command| *command)
+        }
+
+        KeyMatch::Pending
+    }
+
+    /// Descends one level of a key trie, preferring an exact key match and
+    /// falling back to a wildcard character binding.
+    fn step<'a>(node: &'a KeyTrie, key: &Key) -> Option<&'a KeyTrie> {
+        match node {
+            &KeyTrie::Leaf(_) => None,
+            &KeyTrie::Node(ref node) => {
+                if let &Key::Char(_) = key {
+                    node.get(key).or_else(|| node.get(&Key::AnyChar))
+                } else {
+                    node.get(key)
+                }
+            }
+        }
+    }
+
+    /// Searches the keymap for the specified (single) key, returning the
+    /// whole group of commands bound there (most bindings are a single
+    /// command, but see `CommandGroup`). Character keys will fall back to
+    /// wildcard character bindings if the specific character binding
+    /// cannot be found.
+    ///
+    pub fn command_for(&self, mode: &str, key: &Key) -> Option<CommandGroup> {
+        match self.lookup(mode, &[*key]) {
+            KeyMatch::Matched(group) => Some(group),
+            KeyMatch::Pending | KeyMatch::NotFound => None,
+        }
+    }
+
+    /// Feeds a single key into a mode's in-progress chord state, the
+    /// sticky-submenu-aware counterpart to `lookup`. `Esc` always resets
+    /// both the pending keys and the active sticky submenu; a match or
+    /// dead end clears the pending keys but leaves a sticky submenu active
+    /// (it's only exited via `Esc`), re-entering it automatically for the
+    /// next key fed in.
+    pub fn feed(&self, mode: &str, state: &mut ChordState, key: Key) -> KeyMatch {
+        if key == Key::Esc {
+            state.sticky_path.clear();
+            state.pending.clear();
+            return KeyMatch::NotFound;
+        }
+
+        state.pending.push(key);
+
+        let mut keys = state.sticky_path.clone();
+        keys.extend_from_slice(&state.pending);
+
+        let (result, sticky_path) = self.walk(mode, &keys);
+
+        match result {
+            KeyMatch::Matched(_) => {
+                state.sticky_path = sticky_path.unwrap_or_default();
+                state.pending.clear();
+            }
+            KeyMatch::NotFound => state.pending.clear(),
+            KeyMatch::Pending => {
+                // Entering a sticky node makes it the active root right away.
+                if let Some(path) = sticky_path {
+                    state.pending = keys[path.len()..].to_vec();
+                    state.sticky_path = path;
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Like `lookup`, but also returns the path (from the mode root) to
+    /// the deepest sticky node traversed along the way, if any, so `feed`
+    /// can keep a sticky submenu active across multiple commands.
+    fn walk(&self, mode: &str, keys: &[Key]) -> (KeyMatch, Option<Vec<Key>>) {
+        let mut node = match self.0.get(mode) {
+            Some(node) => node,
+            None => return (KeyMatch::NotFound, None),
+        };
+        let mut sticky_path = None;
+        let mut path = Vec::new();
+
+        for key in keys {
+            match KeyMap::step(node, key) {
+                Some(&KeyTrie::Leaf(ref group)) => {
+                    return (KeyMatch::Matched(group.clone()), sticky_path)
+                }
+                Some(next_node @ &KeyTrie::Node(ref trie_node)) => {
+                    path.push(*key);
+                    if trie_node.sticky {
+                        sticky_path = Some(path.clone());
+                    }
+                    node = next_node;
+                }
+                None => return (KeyMatch::NotFound, None),
+            }
+        }
+
+        (KeyMatch::Pending, sticky_path)
+    }
+
+    /// Inverts a mode's key trie, mapping each bound command to every key
+    /// sequence (single key or chord) that triggers it. Useful for a
+    /// command palette or a "what's the shortcut for X" lookup. Commands
+    /// bound as part of a multi-command group are each listed individually.
+    pub fn reverse_map(&self, mode: &str) -> HashMap<Command, Vec<Vec<Key>>> {
+        let mut reverse_map = HashMap::new();
+
+        if let Some(root) = self.0.get(mode) {
+            let mut path = Vec::new();
+            KeyMap::collect_reverse_bindings(root, &mut path, &mut reverse_map);
+        }
+
+        reverse_map
+    }
+
+    /// Walks a key trie depth-first, recording the path of keys leading to
+    /// each leaf against the commands found there.
+    fn collect_reverse_bindings(
+        node: &KeyTrie,
+        path: &mut Vec<Key>,
+        reverse_map: &mut HashMap<Command, Vec<Vec<Key>>>,
+    ) {
+        match node {
+            &KeyTrie::Leaf(ref group) => {
+                for command in group.iter() {
+                    reverse_map.entry(*command).or_insert_with(Vec::new).push(
+                        path.clone(),
+                    );
+                }
+            }
+            &KeyTrie::Node(ref node) => {
+                for (key, child) in node.iter() {
+                    path.push(*key);
+                    KeyMap::collect_reverse_bindings(child, path, reverse_map);
+                    path.pop();
+                }
+            }
+        }
+    }
+
+    /// Returns which-key-style info for a mode's current chord state: the
+    /// active node's label (if any), and a description of each of its
+    /// immediate children, sorted for stable display. Returns `None` if
+    /// the pending keys don't resolve to a node (a dead end, or a
+    /// complete, non-chord binding).
+    pub fn autoinfo(&self, mode: &str, pending: &[Key]) -> Option<(Option<String>, Vec<(Key, String)>)> {
+        let mut node = match self.0.get(mode) {
+            Some(node) => node,
+            None => return None,
+        };
+
+        for key in pending {
+            match KeyMap::step(node, key) {
+                Some(next) => node = next,
+                None => return None,
+            }
+        }
+
+        match node {
+            &KeyTrie::Leaf(_) => None,
+            &KeyTrie::Node(ref node) => {
+                let mut children: Vec<(Key, String)> = node.iter()
+                    .map(|(key, trie)| (*key, describe(trie)))
+                    .collect();
+                children.sort_by_key(|&(key, _)| format!("{:?}", key));
+
+                Some((node.label.clone(), children))
+            }
+        }
     }
 
     pub fn default() -> Result<KeyMap> {
@@ -69,6 +369,9 @@ impl KeyMap {
 
     /// Merges each of the passed key map's modes, consuming them in the process.
     /// Note: the mode must exist to be merged; unmatched modes are discarded.
+    /// Merging only descends one level into each mode's trie; a key bound
+    /// in the other key map entirely replaces any binding (leaf or node)
+    /// at that key in this one.
     ///
     /// e.g.
     ///
@@ -84,60 +387,133 @@ impl KeyMap {
     ///
     /// becomes this:
     ///
-    ///   "normal" => {
-    ///       Key::Ctrl('r') => commands::cursor::move_up
-    ///       Key::Ctrl('s') => commands::cursor::move_down
-    ///   }
+    ///   "normal" => Node({
+    ///       Key::Ctrl('r') => Leaf(commands::cursor::move_up)
+    ///       Key::Ctrl('s') => Leaf(commands::cursor::move_down)
+    ///   })
     ///
     pub fn merge(&mut self, mut key_map: KeyMap) {
         // Step through the specified key map's modes.
-        for (mode, other_key_bindings) in key_map.iter_mut() {
+        for (mode, other_trie) in key_map.iter_mut() {
             // Fetch the current key bindings for the specified mode.
-            if let Some(mut key_bindings) = self.get_mut(mode) {
-                for (key, command) in other_key_bindings.drain() {
-                    key_bindings.insert(key, command);
+            if let Some(&mut KeyTrie::Node(ref mut node)) = self.get_mut(mode) {
+                if let &mut KeyTrie::Node(ref mut other_node) = other_trie {
+                    for (key, trie) in other_node.drain() {
+                        node.insert(key, trie);
+                    }
                 }
             }
         }
     }
 }
 
-/// Parses the key bindings for a particular mode.
+/// A short human-readable description of a trie entry, used to populate
+/// an autoinfo/which-key popup: a node's label (falling back to a
+/// placeholder for unlabeled submenus), or a leaf's command name.
+fn describe(trie: &KeyTrie) -> String {
+    match trie {
+        &KeyTrie::Leaf(ref group) => {
+            group.iter()
+                .map(|&command| command_name(command).unwrap_or("<unknown>"))
+                .collect::<Vec<&str>>()
+                .join(", ")
+        }
+        &KeyTrie::Node(ref node) => node.label.clone().unwrap_or_else(|| "...".to_string()),
+    }
+}
+
+/// Finds the name a command was registered under, for display purposes.
+fn command_name(command: Command) -> Option<&'static str> {
+    commands::hash_map().into_iter().find(|&(_, c)| {
+        (c as *const usize) == (command as *const usize)
+    }).map(|(name, _)| name)
+}
+
+/// Looks up a named command, producing a useful error if it isn't registered.
+fn lookup_command<'a>(commands: &'a HashMap<&str, Command>, command_string: &str) -> Result<&'a Command> {
+    commands.get(command_string).ok_or(
+        format!("Keymap command \"{}\" doesn't exist", command_string).into(),
+    )
+}
+
+/// Parses the key bindings for a particular mode into a `KeyTrie`.
 ///
 /// e.g.
 ///
 ///   ctrl-r: cursor::move_up
 ///
-/// becomes this HashMap entry:
+/// becomes this `KeyTrie`:
 ///
-///   Key::Ctrl('r') => commands::cursor::move_up
+///   Node({ Key::Ctrl('r') => Leaf(commands::cursor::move_up) })
 ///
-fn parse_mode_key_bindings(mode: &Yaml, commands: &HashMap<&str, Command>) -> Result<HashMap<Key, Command>> {
+/// A nested hash produces a nested `Node`, allowing multi-key chords
+/// (e.g. `g: { g: cursor::move_to_top }`) to be bound.
+///
+fn parse_mode_key_bindings(mode: &Yaml, commands: &HashMap<&str, Command>) -> Result<KeyTrie> {
     let mode_key_bindings = mode.as_hash().ok_or(
         "Keymap mode config didn't return a hash of key bindings",
     )?;
 
-    let mut key_bindings = HashMap::new();
-    for (yaml_key, yaml_command) in mode_key_bindings {
+    let mut node = KeyTrieNode::new();
+    for (yaml_key, yaml_value) in mode_key_bindings {
+        // A "label" entry isn't a binding; it names the submenu for a
+        // which-key-style autoinfo popup.
+        if yaml_key.as_str() == Some("label") {
+            node.label = Some(yaml_value.as_str().ok_or(
+                "A keymap label couldn't be parsed as a string",
+            )?.to_string());
+            continue;
+        }
+
+        // A "sticky" entry marks this node as one that, once entered,
+        // stays active across multiple commands instead of resetting
+        // back to the mode root after each one.
+        if yaml_key.as_str() == Some("sticky") {
+            node.sticky = yaml_value.as_bool().ok_or(
+                "A keymap \"sticky\" flag must be a boolean",
+            )?;
+            continue;
+        }
+
         // Parse modifier/character from key component.
         let key = parse_key(yaml_key.as_str().ok_or(format!(
             "A keymap key couldn't be parsed as a string"
         ))?)?;
 
-        // Parse and find command reference from command component.
-        let command_string = yaml_command.as_str().ok_or(format!(
-            "A keymap command couldn't be parsed as a string"
-        ))?;
-        let command = commands.get(command_string).ok_or(format!(
-            "Keymap command \"{}\" doesn't exist",
-            command_string
-        ))?;
+        // A string value is a single-command leaf, a list is a leaf that
+        // runs several commands in sequence, and a nested hash is another
+        // level of the trie (a chord prefix).
+        let trie = if let Some(command_string) = yaml_value.as_str() {
+            KeyTrie::Leaf(CommandGroup::single(
+                *lookup_command(commands, command_string)?,
+            ))
+        } else if let Some(yaml_commands) = yaml_value.as_vec() {
+            let mut group = Vec::with_capacity(yaml_commands.len());
+            for yaml_command in yaml_commands {
+                let command_string = yaml_command.as_str().ok_or(format!(
+                    "A keymap command couldn't be parsed as a string"
+                ))?;
+                group.push(*lookup_command(commands, command_string)?);
+            }
+
+            KeyTrie::Leaf(CommandGroup(group))
+        } else if yaml_value.as_hash().is_some() {
+            parse_mode_key_bindings(yaml_value, commands).chain_err(
+                || "Failed to parse a chord's nested key bindings",
+            )?
+        } else {
+            bail!(format!(
+                "Keymap value for key \"{}\" is neither a command, a list of commands, \
+                 nor a nested set of bindings",
+                yaml_key.as_str().unwrap_or("?")
+            ));
+        };
 
-        // Add a key/command entry to the mapping.
-        key_bindings.insert(key, *command);
+        // Add a key/trie entry to the mapping, in declaration order.
+        node.insert(key, trie);
     }
 
-    Ok(key_bindings)
+    Ok(KeyTrie::Node(node))
 }
 
 /// Parses a str-based key into its Key equivalent.
@@ -147,68 +523,109 @@ fn parse_mode_key_bindings(mode: &Yaml, commands: &HashMap<&str, Command>) -> Re
 ///   ctrl-r becomes Key::Ctrl('r')
 ///
 fn parse_key(data: &str) -> Result<Key> {
-    let mut key_components = data.split("-");
-    let component = key_components.next().ok_or(
+    // Every component but the last is a modifier flag; the last is the key
+    // itself (e.g. "ctrl-shift-x" => ["ctrl", "shift", "x"]).
+    let mut components: Vec<&str> = data.split("-").collect();
+    let key_component = components.pop().ok_or(
         "A keymap key is an empty string",
     )?;
 
-    if let Some(key) = key_components.next() {
-        // We have a modifier-qualified key; get the key.
-        let key_char = key.chars().nth(0).ok_or(format!(
-            "Keymap key \"{}\" is invalid",
-            key
-        ))?;
-
-        // Find the variant for the specified modifier.
-        match component {
-            "ctrl" => Ok(Key::Ctrl(key_char)),
-            _ => bail!(format!("Keymap modifier \"{}\" is invalid", component)),
+    if components.is_empty() && key_component == "_" {
+        return Ok(Key::AnyChar);
+    }
+
+    let mut modifiers = Modifiers::default();
+    for modifier in &components {
+        match *modifier {
+            "ctrl" => modifiers.ctrl = true,
+            "alt" => modifiers.alt = true,
+            "shift" => modifiers.shift = true,
+            _ => bail!(format!("Keymap modifier \"{}\" is invalid", modifier)),
         }
-    } else {
-        // No modifier; just get the key.
-        Ok(match component {
-            "space"     => Key::Char(' '),
-            "backspace" => Key::Backspace,
-            "left"      => Key::Left,
-            "right"     => Key::Right,
-            "up"        => Key::Up,
-            "down"      => Key::Down,
-            "home"      => Key::Home,
-            "end"       => Key::End,
-            "page_up"   => Key::PageUp,
-            "page_down" => Key::PageDown,
-            "delete"    => Key::Delete,
-            "insert"    => Key::Insert,
-            "escape"    => Key::Esc,
-            "tab"       => Key::Tab,
-            "enter"     => Key::Enter,
-            "_"         => Key::AnyChar,
-            _           => Key::Char(
+    }
+
+    let key_code = parse_key_code(key_component)?;
+
+    Ok(match (modifiers.ctrl, modifiers.alt, modifiers.shift) {
+        (false, false, false) => key_code.into(),
+        (true, false, false) => {
+            if let KeyCode::Char(c) = key_code {
+                Key::Ctrl(c)
+            } else {
+                Key::Modified(modifiers, key_code)
+            }
+        }
+        (false, true, false) => {
+            if let KeyCode::Char(c) = key_code {
+                Key::Alt(c)
+            } else {
+                Key::Modified(modifiers, key_code)
+            }
+        }
+        _ => Key::Modified(modifiers, key_code),
+    })
+}
+
+/// Parses the non-modifier portion of a key string (what's left once any
+/// `ctrl-`/`alt-`/`shift-` prefixes have been split off).
+fn parse_key_code(component: &str) -> Result<KeyCode> {
+    Ok(match component {
+        "space"     => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        "left"      => KeyCode::Left,
+        "right"     => KeyCode::Right,
+        "up"        => KeyCode::Up,
+        "down"      => KeyCode::Down,
+        "home"      => KeyCode::Home,
+        "end"       => KeyCode::End,
+        "page_up"   => KeyCode::PageUp,
+        "page_down" => KeyCode::PageDown,
+        "delete"    => KeyCode::Delete,
+        "insert"    => KeyCode::Insert,
+        "escape"    => KeyCode::Esc,
+        "tab"       => KeyCode::Tab,
+        "enter"     => KeyCode::Enter,
+        _           => {
+            if let Some(function_number) = parse_function_key(component) {
+                KeyCode::F(function_number)
+            } else {
                 // It's not a keyword; take its first character, if available.
-                component.chars().nth(0).ok_or(
+                KeyCode::Char(component.chars().nth(0).ok_or(
                     format!("Keymap key \"{}\" is invalid", component)
-                )?
-            ),
-        })
+                )?)
+            }
+        }
+    })
+}
+
+/// Parses "f1" through "f12" into their function key number.
+fn parse_function_key(component: &str) -> Option<u8> {
+    if !component.starts_with('f') {
+        return None;
+    }
+
+    match component[1..].parse::<u8>() {
+        Ok(number) if (1..=12).contains(&number) => Some(number),
+        _ => None,
     }
 }
 
 impl Deref for KeyMap {
-    type Target = HashMap<String, HashMap<Key, Command>>;
+    type Target = HashMap<String, KeyTrie>;
 
-    fn deref(&self) -> &HashMap<String, HashMap<Key, Command>> {
+    fn deref(&self) -> &HashMap<String, KeyTrie> {
         &self.0
     }
 }
 
 impl DerefMut for KeyMap {
-    fn deref_mut(&mut self) -> &mut HashMap<String, HashMap<Key, Command>> {
+    fn deref_mut(&mut self) -> &mut HashMap<String, KeyTrie> {
         &mut self.0
     }
 }
 
-impl Into<HashMap<String, HashMap<Key, Command>>> for KeyMap {
-    fn into(self) -> HashMap<String, HashMap<Key, Command>> {
+impl Into<HashMap<String, KeyTrie>> for KeyMap {
+    fn into(self) -> HashMap<String, KeyTrie> {
         self.0
     }
 }
@@ -216,7 +633,7 @@ impl Into<HashMap<String, HashMap<Key, Command>>> for KeyMap {
 #[cfg(test)]
 mod tests {
     use yaml::YamlLoader;
-    use super::KeyMap;
+    use super::{ChordState, KeyMap, KeyMatch};
     use commands;
     use input::Key;
 
@@ -231,7 +648,7 @@ mod tests {
             "Keymap doesn't contain command",
         );
         assert_eq!(
-            (command as *const usize),
+            (command[0] as *const usize),
             (commands::cursor::move_up as *const usize)
         );
     }
@@ -249,7 +666,7 @@ mod tests {
                 "Keymap doesn't contain command",
             );
             assert_eq!(
-                (command as *const usize),
+                (command[0] as *const usize),
                 (commands::cursor::move_up as *const usize)
             );
         }
@@ -266,14 +683,14 @@ mod tests {
             "Keymap doesn't contain command",
         );
         assert_eq!(
-            (char_command as *const usize),
+            (char_command[0] as *const usize),
             (commands::cursor::move_down as *const usize)
         );
         let wildcard_command = keymap.command_for("normal", &Key::Char('a')).expect(
             "Keymap doesn't contain command",
         );
         assert_eq!(
-            (wildcard_command as *const usize),
+            (wildcard_command[0] as *const usize),
             (commands::cursor::move_up as *const usize)
         );
     }
@@ -289,7 +706,79 @@ mod tests {
             "Keymap doesn't contain command",
         );
         assert_eq!(
-            (command as *const usize),
+            (command[0] as *const usize),
+            (commands::cursor::move_up as *const usize)
+        );
+    }
+
+    #[test]
+    fn keymap_correctly_parses_yaml_alt_keybindings() {
+        let yaml_data = "normal:\n  alt-r: cursor::move_up";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0]).unwrap();
+
+        let command = keymap.command_for("normal", &Key::Alt('r')).expect(
+            "Keymap doesn't contain command",
+        );
+        assert_eq!(
+            (command[0] as *const usize),
+            (commands::cursor::move_up as *const usize)
+        );
+    }
+
+    #[test]
+    fn keymap_correctly_parses_yaml_function_keybindings() {
+        let yaml_data = "normal:\n  f5: cursor::move_up";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0]).unwrap();
+
+        let command = keymap.command_for("normal", &Key::F(5)).expect(
+            "Keymap doesn't contain command",
+        );
+        assert_eq!(
+            (command[0] as *const usize),
+            (commands::cursor::move_up as *const usize)
+        );
+    }
+
+    #[test]
+    fn keymap_correctly_parses_yaml_composite_modifier_keybindings() {
+        let mappings = vec![
+            ("normal:\n  ctrl-shift-x: cursor::move_up", super::Key::Modified(
+                super::Modifiers { ctrl: true, alt: false, shift: true },
+                super::KeyCode::Char('x'),
+            )),
+            ("normal:\n  ctrl-alt-enter: cursor::move_up", super::Key::Modified(
+                super::Modifiers { ctrl: true, alt: true, shift: false },
+                super::KeyCode::Enter,
+            )),
+        ];
+
+        for (binding, key) in mappings {
+            let yaml = YamlLoader::load_from_str(binding).unwrap();
+            let keymap = KeyMap::from(&yaml[0]).unwrap();
+
+            let command = keymap.command_for("normal", &key).expect(
+                "Keymap doesn't contain command",
+            );
+            assert_eq!(
+                (command[0] as *const usize),
+                (commands::cursor::move_up as *const usize)
+            );
+        }
+    }
+
+    #[test]
+    fn keymap_correctly_parses_yaml_ctrl_space_keybindings() {
+        let yaml_data = "normal:\n  ctrl-space: cursor::move_up";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0]).unwrap();
+
+        let command = keymap.command_for("normal", &Key::Ctrl(' ')).expect(
+            "Keymap doesn't contain command",
+        );
+        assert_eq!(
+            (command[0] as *const usize),
             (commands::cursor::move_up as *const usize)
         );
     }
@@ -320,7 +809,7 @@ mod tests {
             let keymap = KeyMap::from(&yaml[0]).unwrap();
 
             let parsed_command = keymap.command_for("normal", &key).expect("Keymap doesn't contain command");
-            assert_eq!((parsed_command as *const usize), (command as *const usize));
+            assert_eq!((parsed_command[0] as *const usize), (command as *const usize));
         }
     }
 
@@ -333,7 +822,7 @@ mod tests {
             "Keymap doesn't contain command",
         );
         assert_eq!(
-            (command as *const usize),
+            (command[0] as *const usize),
             (commands::cursor::move_up as *const usize)
         );
     }
@@ -354,7 +843,7 @@ mod tests {
             "Keymap doesn't contain original command",
         );
         assert_eq!(
-            (command as *const usize),
+            (command[0] as *const usize),
             (commands::cursor::move_down as *const usize)
         );
 
@@ -362,7 +851,7 @@ mod tests {
             "Keymap doesn't contain overlapping command",
         );
         assert_eq!(
-            (command as *const usize),
+            (command[0] as *const usize),
             (commands::cursor::move_left as *const usize)
         );
 
@@ -370,8 +859,219 @@ mod tests {
             "Keymap doesn't contain other command",
         );
         assert_eq!(
-            (command as *const usize),
+            (command[0] as *const usize),
             (commands::cursor::move_right as *const usize)
         );
     }
+
+    #[test]
+    fn lookup_matches_a_single_key_binding() {
+        let yaml_data = "normal:\n  k: cursor::move_up";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0]).unwrap();
+
+        match keymap.lookup("normal", &[Key::Char('k')]) {
+            KeyMatch::Matched(command) => assert_eq!(
+                (command[0] as *const usize),
+                (commands::cursor::move_up as *const usize)
+            ),
+            _ => panic!("Expected a match"),
+        }
+    }
+
+    #[test]
+    fn lookup_reports_pending_for_a_valid_chord_prefix() {
+        let yaml_data = "normal:\n  g:\n    g: cursor::move_to_top";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0]).unwrap();
+
+        assert!(keymap.lookup("normal", &[Key::Char('g')]) == KeyMatch::Pending);
+    }
+
+    #[test]
+    fn lookup_matches_a_complete_chord() {
+        let yaml_data = "normal:\n  g:\n    g: cursor::move_to_top";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0]).unwrap();
+
+        match keymap.lookup("normal", &[Key::Char('g'), Key::Char('g')]) {
+            KeyMatch::Matched(command) => assert_eq!(
+                (command[0] as *const usize),
+                (commands::cursor::move_to_top as *const usize)
+            ),
+            _ => panic!("Expected a match"),
+        }
+    }
+
+    #[test]
+    fn lookup_reports_not_found_for_a_dead_end_chord() {
+        let yaml_data = "normal:\n  g:\n    g: cursor::move_to_top";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0]).unwrap();
+
+        assert!(keymap.lookup("normal", &[Key::Char('g'), Key::Char('x')]) == KeyMatch::NotFound);
+    }
+
+    #[test]
+    fn lookup_falls_back_to_wildcard_character_bindings_within_a_chord() {
+        let yaml_data = "normal:\n  g:\n    _: cursor::move_to_top";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0]).unwrap();
+
+        match keymap.lookup("normal", &[Key::Char('g'), Key::Char('z')]) {
+            KeyMatch::Matched(command) => assert_eq!(
+                (command[0] as *const usize),
+                (commands::cursor::move_to_top as *const usize)
+            ),
+            _ => panic!("Expected a match"),
+        }
+    }
+
+    #[test]
+    fn reverse_map_lists_every_key_bound_to_a_command() {
+        let yaml_data = "normal:\n  k: cursor::move_up\n  up: cursor::move_up\n  j: cursor::move_down";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0]).unwrap();
+
+        let reverse_map = keymap.reverse_map("normal");
+        let move_up_bindings = reverse_map.get(&(commands::cursor::move_up as commands::Command)).expect(
+            "Reverse map doesn't contain command",
+        );
+
+        assert_eq!(move_up_bindings.len(), 2);
+        assert!(move_up_bindings.contains(&vec![Key::Char('k')]));
+        assert!(move_up_bindings.contains(&vec![Key::Up]));
+    }
+
+    #[test]
+    fn reverse_map_includes_the_full_path_of_a_chord() {
+        let yaml_data = "normal:\n  g:\n    g: cursor::move_to_top";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0]).unwrap();
+
+        let reverse_map = keymap.reverse_map("normal");
+        let bindings = reverse_map.get(&(commands::cursor::move_to_top as commands::Command)).expect(
+            "Reverse map doesn't contain command",
+        );
+
+        assert_eq!(bindings, &vec![vec![Key::Char('g'), Key::Char('g')]]);
+    }
+
+    #[test]
+    fn autoinfo_includes_a_labeled_node_and_its_children() {
+        let yaml_data = "normal:\n  g:\n    label: \"Goto\"\n    g: cursor::move_to_top\n    e: cursor::move_to_bottom";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0]).unwrap();
+
+        let (label, children) = keymap.autoinfo("normal", &[Key::Char('g')]).expect(
+            "Expected an autoinfo node",
+        );
+
+        assert_eq!(label, Some("Goto".to_string()));
+        assert_eq!(
+            children,
+            vec![
+                (Key::Char('e'), "cursor::move_to_bottom".to_string()),
+                (Key::Char('g'), "cursor::move_to_top".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn autoinfo_returns_none_for_a_complete_binding() {
+        let yaml_data = "normal:\n  k: cursor::move_up";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0]).unwrap();
+
+        assert!(keymap.autoinfo("normal", &[Key::Char('k')]).is_none());
+    }
+
+    #[test]
+    fn keymap_correctly_parses_a_list_of_commands_for_one_key() {
+        let yaml_data = "normal:\n  d: [selection::copy, buffer::delete_selection]";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0]).unwrap();
+
+        let group = keymap.command_for("normal", &Key::Char('d')).expect(
+            "Keymap doesn't contain command group",
+        );
+
+        assert_eq!(group.len(), 2);
+        assert_eq!(
+            (group[0] as *const usize),
+            (commands::selection::copy as *const usize)
+        );
+        assert_eq!(
+            (group[1] as *const usize),
+            (commands::buffer::delete_selection as *const usize)
+        );
+    }
+
+    #[test]
+    fn keymap_wraps_a_single_command_in_a_one_element_group() {
+        let yaml_data = "normal:\n  k: cursor::move_up";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0]).unwrap();
+
+        let group = keymap.command_for("normal", &Key::Char('k')).expect(
+            "Keymap doesn't contain command group",
+        );
+
+        assert_eq!(group.len(), 1);
+    }
+
+    #[test]
+    fn feed_keeps_a_sticky_submenu_active_after_a_match() {
+        let yaml_data = "normal:\n  \
+                          z:\n    sticky: true\n    j: cursor::move_down\n    k: cursor::move_up";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0]).unwrap();
+        let mut state = ChordState::new();
+
+        assert!(keymap.feed("normal", &mut state, Key::Char('z')) == KeyMatch::Pending);
+        match keymap.feed("normal", &mut state, Key::Char('j')) {
+            KeyMatch::Matched(command) => assert_eq!(
+                (command[0] as *const usize),
+                (commands::cursor::move_down as *const usize)
+            ),
+            _ => panic!("Expected a match"),
+        }
+
+        // The sticky submenu is still active, so a bare "k" (without
+        // retyping the "z" prefix) should resolve directly.
+        match keymap.feed("normal", &mut state, Key::Char('k')) {
+            KeyMatch::Matched(command) => assert_eq!(
+                (command[0] as *const usize),
+                (commands::cursor::move_up as *const usize)
+            ),
+            _ => panic!("Expected the sticky submenu to still be active"),
+        }
+    }
+
+    #[test]
+    fn feed_esc_clears_the_sticky_submenu() {
+        let yaml_data = "normal:\n  \
+                          z:\n    sticky: true\n    j: cursor::move_down\n  \
+                          k: cursor::move_up";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0]).unwrap();
+        let mut state = ChordState::new();
+
+        keymap.feed("normal", &mut state, Key::Char('z'));
+        keymap.feed("normal", &mut state, Key::Char('j'));
+        assert!(state.is_sticky());
+
+        assert!(keymap.feed("normal", &mut state, Key::Esc) == KeyMatch::NotFound);
+        assert!(!state.is_sticky());
+
+        // Back at the mode root, "k" resolves to the top-level binding
+        // rather than a (nonexistent) child of the sticky submenu.
+        match keymap.feed("normal", &mut state, Key::Char('k')) {
+            KeyMatch::Matched(command) => assert_eq!(
+                (command[0] as *const usize),
+                (commands::cursor::move_up as *const usize)
+            ),
+            _ => panic!("Expected a match at the mode root"),
+        }
+    }
 }
\ No newline at end of file
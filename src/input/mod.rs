@@ -0,0 +1,85 @@
+pub mod key_map;
+
+/// Modifier flags that can accompany a key press (e.g. the `ctrl`/`alt`/
+/// `shift` in `ctrl-shift-x`). `Key::Ctrl` and `Key::Alt` cover the common
+/// single-modifier case directly; combinations fall back to
+/// `Key::Modified`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+
+/// The non-modifier portion of a key, used as the payload for
+/// `Key::Modified` so that any key (character, named, or function key)
+/// can be combined with more than one modifier.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum KeyCode {
+    Char(char),
+    Backspace,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Delete,
+    Insert,
+    Esc,
+    Tab,
+    Enter,
+    F(u8),
+}
+
+impl From<KeyCode> for Key {
+    fn from(code: KeyCode) -> Key {
+        match code {
+            KeyCode::Char(c) => Key::Char(c),
+            KeyCode::Backspace => Key::Backspace,
+            KeyCode::Left => Key::Left,
+            KeyCode::Right => Key::Right,
+            KeyCode::Up => Key::Up,
+            KeyCode::Down => Key::Down,
+            KeyCode::Home => Key::Home,
+            KeyCode::End => Key::End,
+            KeyCode::PageUp => Key::PageUp,
+            KeyCode::PageDown => Key::PageDown,
+            KeyCode::Delete => Key::Delete,
+            KeyCode::Insert => Key::Insert,
+            KeyCode::Esc => Key::Esc,
+            KeyCode::Tab => Key::Tab,
+            KeyCode::Enter => Key::Enter,
+            KeyCode::F(n) => Key::F(n),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Key {
+    Backspace,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Delete,
+    Insert,
+    Esc,
+    Tab,
+    Enter,
+    Char(char),
+    Ctrl(char),
+    Alt(char),
+    F(u8),
+    AnyChar,
+    /// A key combined with more than one modifier, with `shift`, or with a
+    /// bare `ctrl`/`alt` on a non-character key (e.g. `ctrl-shift-x`,
+    /// `ctrl-alt-enter`, `ctrl-enter`).
+    Modified(Modifiers, KeyCode),
+}